@@ -0,0 +1,220 @@
+//! A minimal complex number type so a [`Computer`](crate::computer::Computer)
+//! can evaluate expressions like `sqrt(-1)` over ℂ instead of producing `NaN`.
+//! ```
+//! use rsc::{computer::Computer, complex::Complex};
+//!
+//! let mut computer = Computer::<Complex<f64>>::new(
+//!     Complex::new(std::f64::consts::PI, 0.0),
+//!     Complex::new(std::f64::consts::E, 0.0),
+//! );
+//! assert_eq!(computer.eval("sqrt(-1)").unwrap(), Complex::new(0.0, 1.0));
+//!
+//! // `0^0` matches the `f64` backend's convention (`1`) instead of the `NaN`
+//! // the general `exp(w * ln(z))` formula would otherwise produce.
+//! assert_eq!(computer.eval("0^0").unwrap(), Complex::new(1.0, 0.0));
+//! ```
+
+use crate::computer::Num;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Complex<T> {
+    pub re: T,
+    pub im: T,
+}
+
+impl<T> Complex<T> {
+    pub fn new(re: T, im: T) -> Complex<T> {
+        Complex { re, im }
+    }
+}
+
+impl std::str::FromStr for Complex<f64> {
+    type Err = std::num::ParseFloatError;
+
+    /// Parses a plain real literal (as produced by the lexer for a bare
+    /// number) into a complex number with a zero imaginary part.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<f64>().map(|re| Complex::new(re, 0.0))
+    }
+}
+
+impl Add for Complex<f64> {
+    type Output = Complex<f64>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Complex::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl Sub for Complex<f64> {
+    type Output = Complex<f64>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Complex::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl Mul for Complex<f64> {
+    type Output = Complex<f64>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Complex::new(
+            self.re * rhs.re - self.im * rhs.im,
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+}
+
+impl Div for Complex<f64> {
+    type Output = Complex<f64>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        let denom = rhs.re * rhs.re + rhs.im * rhs.im;
+        Complex::new(
+            (self.re * rhs.re + self.im * rhs.im) / denom,
+            (self.im * rhs.re - self.re * rhs.im) / denom,
+        )
+    }
+}
+
+impl Neg for Complex<f64> {
+    type Output = Complex<f64>;
+
+    fn neg(self) -> Self::Output {
+        Complex::new(-self.re, -self.im)
+    }
+}
+
+impl PartialOrd for Complex<f64> {
+    /// Only purely real complex numbers (`im == 0`) have a well-defined
+    /// order; anything else is incomparable, same as the rest of `PartialOrd`'s
+    /// contract.
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        if self.im == 0.0 && other.im == 0.0 {
+            self.re.partial_cmp(&other.re)
+        } else {
+            None
+        }
+    }
+}
+
+impl Complex<f64> {
+    fn magnitude(&self) -> f64 {
+        (self.re * self.re + self.im * self.im).sqrt()
+    }
+
+    fn exp(&self) -> Complex<f64> {
+        let factor = self.re.exp();
+        Complex::new(factor * self.im.cos(), factor * self.im.sin())
+    }
+
+    fn ln(&self) -> Complex<f64> {
+        Complex::new(self.magnitude().ln(), self.im.atan2(self.re))
+    }
+}
+
+impl Num for Complex<f64> {
+    fn zero() -> Self {
+        Complex::new(0.0, 0.0)
+    }
+
+    fn one() -> Self {
+        Complex::new(1.0, 0.0)
+    }
+
+    fn is_integer(&self) -> bool {
+        self.im == 0.0 && self.re.fract() == 0.0
+    }
+
+    fn sqrt(&self) -> Self {
+        if self.im == 0.0 {
+            return if self.re >= 0.0 {
+                Complex::new(self.re.sqrt(), 0.0)
+            } else {
+                Complex::new(0.0, (-self.re).sqrt())
+            };
+        }
+
+        let r = self.magnitude();
+        let re = ((r + self.re) / 2.0).sqrt();
+        let im = ((r - self.re) / 2.0).sqrt() * if self.im < 0.0 { -1.0 } else { 1.0 };
+        Complex::new(re, im)
+    }
+
+    fn sin(&self) -> Self {
+        Complex::new(
+            self.re.sin() * self.im.cosh(),
+            self.re.cos() * self.im.sinh(),
+        )
+    }
+
+    fn cos(&self) -> Self {
+        Complex::new(
+            self.re.cos() * self.im.cosh(),
+            -self.re.sin() * self.im.sinh(),
+        )
+    }
+
+    fn tan(&self) -> Self {
+        self.sin() / self.cos()
+    }
+
+    fn log(&self) -> Self {
+        self.ln()
+    }
+
+    fn abs(&self) -> Self {
+        Complex::new(self.magnitude(), 0.0)
+    }
+
+    fn pow(&self, other: &Self) -> Self {
+        // `exp(w * ln(z))` is undefined at `z == 0` (`ln(0)` is `-inf`), so
+        // special-case the same two points `f64::powf` defines by
+        // convention: `z^0 == 1` (even `0^0`), and `0^w == 0` for positive
+        // real `w`. Zero to a negative or non-real power is left to the
+        // general formula below, same as before this special-casing.
+        if other.im == 0.0 && other.re == 0.0 {
+            return Complex::new(1.0, 0.0);
+        }
+        if self.im == 0.0 && self.re == 0.0 && other.im == 0.0 && other.re > 0.0 {
+            return Complex::new(0.0, 0.0);
+        }
+
+        (*other * self.ln()).exp()
+    }
+
+    fn from_imaginary(im: f64) -> Self {
+        Complex::new(0.0, im)
+    }
+
+    /// There's no canonical ordering over ℂ, so `min`/`max` fall back to
+    /// comparing magnitude.
+    fn min(&self, other: &Self) -> Self {
+        if self.magnitude() <= other.magnitude() {
+            *self
+        } else {
+            *other
+        }
+    }
+
+    fn max(&self, other: &Self) -> Self {
+        if self.magnitude() >= other.magnitude() {
+            *self
+        } else {
+            *other
+        }
+    }
+
+    fn hypot(&self, other: &Self) -> Self {
+        (*self * *self + *other * *other).sqrt()
+    }
+
+    fn log_base(&self, base: &Self) -> Self {
+        self.log() / base.log()
+    }
+
+    fn root(&self, n: &Self) -> Self {
+        self.pow(&(Complex::new(1.0, 0.0) / *n))
+    }
+}
@@ -23,6 +23,83 @@ pub trait Num {
     fn log(&self) -> Self;
     fn abs(&self) -> Self;
     fn pow(&self, other: &Self) -> Self;
+    /// Build the value `im` times the imaginary unit, for literals like `3i`.
+    /// Types without an imaginary component just fold it back into their
+    /// normal representation.
+    fn from_imaginary(im: f64) -> Self;
+    fn min(&self, other: &Self) -> Self;
+    fn max(&self, other: &Self) -> Self;
+    fn hypot(&self, other: &Self) -> Self;
+    /// `log_base(b)` is `log_b(self)`, i.e. the base-`b` logarithm of `self`.
+    fn log_base(&self, base: &Self) -> Self;
+    /// `root(n)` is the `n`th root of `self`.
+    fn root(&self, n: &Self) -> Self;
+}
+
+impl Num for f64 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn one() -> Self {
+        1.0
+    }
+
+    fn is_integer(&self) -> bool {
+        self.fract() == 0.0
+    }
+
+    fn sqrt(&self) -> Self {
+        f64::sqrt(*self)
+    }
+
+    fn sin(&self) -> Self {
+        f64::sin(*self)
+    }
+
+    fn cos(&self) -> Self {
+        f64::cos(*self)
+    }
+
+    fn tan(&self) -> Self {
+        f64::tan(*self)
+    }
+
+    fn log(&self) -> Self {
+        f64::ln(*self)
+    }
+
+    fn abs(&self) -> Self {
+        f64::abs(*self)
+    }
+
+    fn pow(&self, other: &Self) -> Self {
+        f64::powf(*self, *other)
+    }
+
+    fn from_imaginary(im: f64) -> Self {
+        im
+    }
+
+    fn min(&self, other: &Self) -> Self {
+        f64::min(*self, *other)
+    }
+
+    fn max(&self, other: &Self) -> Self {
+        f64::max(*self, *other)
+    }
+
+    fn hypot(&self, other: &Self) -> Self {
+        f64::hypot(*self, *other)
+    }
+
+    fn log_base(&self, base: &Self) -> Self {
+        f64::log(*self, *base)
+    }
+
+    fn root(&self, n: &Self) -> Self {
+        f64::powf(*self, 1.0 / *n)
+    }
 }
 
 /// # Error Lookup Table
@@ -31,14 +108,103 @@ pub trait Num {
 /// | InvalidFactorial       | When trying to compute a factorial with a decimal or a number less than zero.           |
 /// | VariableIsConstant     | When trying to set a constant variable's value.                                         |
 /// | UnrecognizedIdentifier | When an identifier could not be resolved: it was not found in the Computer's variables. |
+/// | WrongArgumentCount     | When a function was called with a number of arguments it doesn't accept.               |
+///
+/// ```
+/// use rsc::computer::Computer;
+///
+/// let mut computer = Computer::<f64>::new(std::f64::consts::PI, std::f64::consts::E);
+///
+/// // Binary functions, including `log`'s two-argument form (`log(base, x)`).
+/// assert_eq!(computer.eval("log(2, 8)").unwrap(), 3.0);
+/// assert_eq!(computer.eval("min(3, 7)").unwrap(), 3.0);
+/// assert_eq!(computer.eval("max(3, 7)").unwrap(), 7.0);
+/// assert_eq!(computer.eval("hypot(3, 4)").unwrap(), 5.0);
+/// assert_eq!(computer.eval("root(2, 9)").unwrap(), 3.0);
+///
+/// // Calling a function with an arity it doesn't accept is a `WrongArgumentCount`.
+/// assert!(matches!(
+///     computer.eval("min(1, 2, 3)"),
+///     Err(rsc::EvalError::ComputeError(
+///         rsc::computer::ComputeError::WrongArgumentCount { expected: 2, actual: 3, .. }
+///     ))
+/// ));
+/// ```
 #[derive(Debug, Clone, PartialEq)]
 pub enum ComputeError {
-    InvalidFactorial,
-    VariableIsConstant(String),
-    UnrecognizedIdentifier(String),
+    InvalidFactorial(Range<usize>),
+    VariableIsConstant(String, Range<usize>),
+    UnrecognizedIdentifier(String, Range<usize>),
+    WrongArgumentCount {
+        function: String,
+        expected: usize,
+        actual: usize,
+        span: Range<usize>,
+    },
 }
 use self::ComputeError::*;
 
+/// Dispatches a function call once its arguments have already been
+/// evaluated. Shared by the tree-walking `compute_expr` and the bytecode
+/// [`Program`](crate::vm::Program), so both paths agree on arity checking
+/// and the actual math. `span` is attached to `WrongArgumentCount` so it
+/// points at the call; the VM, which has no source spans once compiled,
+/// passes an empty one.
+pub(crate) fn call_function<T: Num + Clone>(
+    function: Function,
+    values: &[T],
+    span: Range<usize>,
+) -> Result<T, ComputeError> {
+    if !function.arities().contains(&values.len()) {
+        let closest = function.arities()[0];
+        let expected = if values.len() < closest {
+            closest
+        } else {
+            *function.arities().last().unwrap()
+        };
+        return Err(WrongArgumentCount {
+            function: function.name().to_string(),
+            expected,
+            actual: values.len(),
+            span,
+        });
+    }
+
+    Ok(match (function, values) {
+        (Function::Sqrt, [a]) => a.sqrt(),
+        (Function::Sin, [a]) => a.sin(),
+        (Function::Cos, [a]) => a.cos(),
+        (Function::Tan, [a]) => a.tan(),
+        (Function::Log, [a]) => a.log(),
+        (Function::Abs, [a]) => a.abs(),
+        (Function::Log, [base, x]) => x.log_base(base),
+        (Function::Min, [a, b]) => a.min(b),
+        (Function::Max, [a, b]) => a.max(b),
+        (Function::Hypot, [a, b]) => a.hypot(b),
+        (Function::Root, [n, x]) => x.root(n),
+        _ => unreachable!("arity already validated above"),
+    })
+}
+
+/// Shared by the tree-walking `compute_expr` and [`Program`](crate::vm::Program).
+pub(crate) fn factorial<T>(mut value: T, span: Range<usize>) -> Result<T, ComputeError>
+where
+    T: Num + Clone + PartialOrd + Sub<Output = T> + Mul<Output = T>,
+{
+    if value < T::zero() || !value.is_integer() {
+        Err(InvalidFactorial(span))
+    } else if value == T::zero() || value == T::one() {
+        Ok(T::one())
+    } else {
+        let mut factor = value.clone() - T::one();
+        while factor > T::one() {
+            value = value * factor.clone();
+            factor = factor - T::one();
+        }
+        Ok(value)
+    }
+}
+
 /// A Computer object calculates expressions and has variables.
 /// ```
 /// use rsc::{
@@ -50,7 +216,7 @@ use self::ComputeError::*;
 /// assert_eq!(computer.eval("a = 2").unwrap(), 2.0);
 /// assert_eq!(computer.eval("a * 3").unwrap(), 6.0);
 ///
-/// // Err(EvalError::ComputeError(ComputeError::UnrecognizedIdentifier("a")))
+/// // Err(EvalError::ComputeError(ComputeError::UnrecognizedIdentifier("a", 0..1)))
 /// Computer::new(std::f64::consts::PI, std::f64::consts::E).eval("a");
 /// ```
 #[derive(Debug, Clone, PartialEq)]
@@ -82,11 +248,11 @@ impl<
 
     /// Lexically analyze, parse, and compute the given equation in string form. This does every step for you,
     /// in a single helper function.
-    pub fn eval(&mut self, expr: &str) -> Result<T, EvalError<T>>
+    pub fn eval(&mut self, expr: &str) -> Result<T, EvalError>
     where
         T: std::str::FromStr,
     {
-        match tokenize(expr, false) {
+        match tokenize(expr) {
             Ok(tokens) => match parse(&tokens) {
                 Ok(ast) => match self.compute(&ast) {
                     Ok(num) => Ok(num),
@@ -98,82 +264,68 @@ impl<
         }
     }
 
-    fn compute_expr(&mut self, expr: &Expr<T>) -> Result<T, ComputeError> {
-        match expr {
+    fn compute_expr(&mut self, expr: &Spanned<Expr<T>>) -> Result<T, ComputeError> {
+        match &expr.node {
             Expr::Constant(num) => Ok(num.clone()),
             Expr::Identifier(id) => match self.variables.get(id) {
                 Some(value) => Ok(value.0.clone()),
-                None => Err(UnrecognizedIdentifier(id.clone())),
+                None => Err(UnrecognizedIdentifier(id.clone(), expr.span.clone())),
             },
-            Expr::Neg(expr) => Ok(-self.compute_expr(expr)?),
+            Expr::Neg(inner) => Ok(-self.compute_expr(inner)?),
             Expr::BinOp(op, lexpr, rexpr) => {
-                let lnum = self.compute_expr(&lexpr)?;
-                let rnum = self.compute_expr(&rexpr)?;
+                let lnum = self.compute_expr(lexpr)?;
+                let rnum = self.compute_expr(rexpr)?;
+
+                let truthy = |cond: bool| if cond { T::one() } else { T::zero() };
 
                 match op {
                     Operator::Plus => Ok(lnum + rnum),
                     Operator::Minus => Ok(lnum - rnum),
                     Operator::Star => Ok(lnum * rnum),
                     Operator::Slash => Ok(lnum / rnum),
-                    _ => unimplemented!(),
+                    Operator::Lt => Ok(truthy(lnum < rnum)),
+                    Operator::Le => Ok(truthy(lnum <= rnum)),
+                    Operator::Gt => Ok(truthy(lnum > rnum)),
+                    Operator::Ge => Ok(truthy(lnum >= rnum)),
+                    Operator::EqEq => Ok(truthy(lnum == rnum)),
+                    Operator::Ne => Ok(truthy(lnum != rnum)),
+                    _ => unreachable!("not a valid BinOp operator"),
                 }
             }
-            Expr::Function(function, expr) => {
-                let num = self.compute_expr(&expr)?;
-                Ok(match function {
-                    Function::Sqrt => num.sqrt(),
-                    Function::Sin => num.sin(),
-                    Function::Cos => num.cos(),
-                    Function::Tan => num.tan(),
-                    Function::Log => num.log(),
-                    Function::Abs => num.abs(),
-                })
+            Expr::Function(function, args) => {
+                let values = args
+                    .iter()
+                    .map(|arg| self.compute_expr(arg))
+                    .collect::<Result<Vec<T>, ComputeError>>()?;
+
+                call_function(*function, &values, expr.span.clone())
             }
-            Expr::Assignment(id, expr) => {
-                let value = self.compute_expr(&expr)?;
-                if self.variables.contains_key(id) {
-                    if self.variables.get(id).unwrap().1 == true {
-                        return Err(VariableIsConstant(id.clone()));
-                    }
+            Expr::Assignment(id, inner) => {
+                let value = self.compute_expr(inner)?;
+                if self.variables.contains_key(id) && self.variables.get(id).unwrap().1 {
+                    return Err(VariableIsConstant(id.clone(), expr.span.clone()));
                 }
                 self.variables.insert(id.clone(), (value.clone(), false));
                 Ok(value)
             }
             Expr::Pow(lexpr, rexpr) => {
-                Ok(self.compute_expr(&lexpr)?.pow(&self.compute_expr(&rexpr)?))
-            }
-            Expr::Factorial(expr) => {
-                let mut value = self.compute_expr(&expr)?;
-                if value < T::zero() || !value.is_integer() {
-                    Err(InvalidFactorial)
-                } else if value == T::zero() || value == T::one() {
-                    Ok(T::one())
-                } else {
-                    let mut factor = value.clone() - T::one();
-                    while factor > T::one() {
-                        value = value * factor.clone();
-                        factor = factor - T::one();
-                    }
-                    Ok(value)
-                }
+                Ok(self.compute_expr(lexpr)?.pow(&self.compute_expr(rexpr)?))
             }
+            Expr::Factorial(inner) => factorial(self.compute_expr(inner)?, expr.span.clone()),
         }
     }
 
     /// Solve an already parsed `Expr` (AST).
-    /// ```
+    /// ```ignore
     /// let ast = parse(/*...*/);
     /// // Using this function to create the result from the `Expr`.
     /// let result = compute(&ast).unwrap();
     /// ```
-    pub fn compute(&mut self, expr: &Expr<T>) -> Result<T, ComputeError> {
+    pub fn compute(&mut self, expr: &Spanned<Expr<T>>) -> Result<T, ComputeError> {
         let val = self.compute_expr(expr);
-        match &val {
-            Ok(n) => {
-                self.variables
-                    .insert(String::from("ans"), (n.clone(), true));
-            }
-            _ => {}
+        if let Ok(n) = &val {
+            self.variables
+                .insert(String::from("ans"), (n.clone(), true));
         }
         val
     }
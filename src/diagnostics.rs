@@ -0,0 +1,81 @@
+//! Turns a byte-offset span from a [`LexerError`], [`ParserError`], or
+//! [`ComputeError`] into a caret-underlined rendering of the offending
+//! source text, the way many interpreters report syntax errors.
+
+use std::ops::Range;
+
+use crate::computer::ComputeError;
+use crate::lexer::LexerError;
+use crate::parser::ParserError;
+use crate::EvalError;
+
+/// Implemented by every error type in the pipeline that can point back at
+/// the span of source text it came from.
+pub trait HasSpan {
+    fn span(&self) -> Range<usize>;
+}
+
+impl HasSpan for LexerError {
+    fn span(&self) -> Range<usize> {
+        match self {
+            LexerError::InvalidCharacter(_, span) => span.clone(),
+            LexerError::InvalidNumber(_, span) => span.clone(),
+        }
+    }
+}
+
+impl HasSpan for ParserError {
+    fn span(&self) -> Range<usize> {
+        match self {
+            ParserError::UnexpectedToken(_, span) => span.clone(),
+            ParserError::UnexpectedEndOfInput(offset) => *offset..*offset,
+            ParserError::UnknownFunction(_, span) => span.clone(),
+            ParserError::InvalidNumber(_, span) => span.clone(),
+        }
+    }
+}
+
+impl HasSpan for ComputeError {
+    fn span(&self) -> Range<usize> {
+        match self {
+            ComputeError::InvalidFactorial(span) => span.clone(),
+            ComputeError::VariableIsConstant(_, span) => span.clone(),
+            ComputeError::UnrecognizedIdentifier(_, span) => span.clone(),
+            ComputeError::WrongArgumentCount { span, .. } => span.clone(),
+        }
+    }
+}
+
+impl HasSpan for EvalError {
+    fn span(&self) -> Range<usize> {
+        match self {
+            EvalError::LexerError(err) => err.span(),
+            EvalError::ParserError(err) => err.span(),
+            EvalError::ComputeError(err) => err.span(),
+        }
+    }
+}
+
+/// Renders `input` followed by a line of carets underlining `error`'s span.
+///
+/// Spans are byte offsets, so on input containing multi-byte UTF-8
+/// characters the underline shifts right by one column for each extra byte
+/// before the span, and its length counts bytes rather than characters.
+/// ```
+/// use rsc::computer::Computer;
+/// use rsc::diagnostics::render_span;
+///
+/// let input = "2 + ? ";
+/// let err = Computer::<f64>::new(std::f64::consts::PI, std::f64::consts::E)
+///     .eval(input)
+///     .unwrap_err();
+/// assert_eq!(render_span(input, &err), "2 + ? \n    ^");
+/// ```
+pub fn render_span(input: &str, error: &impl HasSpan) -> String {
+    let span = error.span();
+    let start = span.start.min(input.len());
+    let end = span.end.min(input.len()).max(start);
+    let underline_len = (end - start).max(1);
+
+    format!("{}\n{}{}", input, " ".repeat(start), "^".repeat(underline_len))
+}
@@ -1,3 +1,23 @@
+use std::ops::Range;
+
+/// The operators the lexer recognizes, including the two-character
+/// relational/equality operators (`<=`, `>=`, `==`, `!=`), which require
+/// one character of lookahead to distinguish from their one-character prefixes.
+/// ```
+/// use rsc::computer::Computer;
+///
+/// let mut computer = Computer::<f64>::new(std::f64::consts::PI, std::f64::consts::E);
+///
+/// // Relational/equality operators evaluate to 1.0 (true) or 0.0 (false),
+/// // and bind looser than `+`/`-`.
+/// assert_eq!(computer.eval("2 + 2 > 3").unwrap(), 1.0);
+/// assert_eq!(computer.eval("2 + 2 >= 4").unwrap(), 1.0);
+/// assert_eq!(computer.eval("2 < 1").unwrap(), 0.0);
+/// assert_eq!(computer.eval("2 <= 2").unwrap(), 1.0);
+/// assert_eq!(computer.eval("2 == 2").unwrap(), 1.0);
+/// assert_eq!(computer.eval("2 != 2").unwrap(), 0.0);
+/// assert_eq!(computer.eval("(2 > 1) * 10").unwrap(), 10.0);
+/// ```
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Operator {
     Plus,
@@ -5,68 +25,148 @@ pub enum Operator {
     Star,
     Slash,
     Caret,
+    Bang,
+    Equals,
+    Comma,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    EqEq,
+    Ne,
     LParen,
     RParen,
 }
 use self::Operator::*;
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Token {
     Number(f64),
+    /// A number immediately followed by the imaginary unit `i`, e.g. `3i` or `2.5i`.
+    ImaginaryNumber(f64),
+    Identifier(String),
     Operator(Operator),
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum LexerError {
-    InvalidCharacter(char),
-    InvalidNumber(String),
+    InvalidCharacter(char, Range<usize>),
+    InvalidNumber(String, Range<usize>),
 }
 
-pub fn tokenize(input: &str) -> Result<Vec<Token>, LexerError> {
-    let mut tokens = Vec::<Token>::new();
+/// Lexically analyzes `input`, returning each [`Token`] paired with the byte
+/// offsets (into `input`) it was read from, so callers can underline the
+/// exact source text behind a later parser or compute error.
+pub fn tokenize(input: &str) -> Result<Vec<(Token, Range<usize>)>, LexerError> {
+    let mut tokens = Vec::<(Token, Range<usize>)>::new();
 
     let chars: Vec<char> = input.chars().collect();
+    let mut byte_offsets: Vec<usize> = input.char_indices().map(|(byte, _)| byte).collect();
+    byte_offsets.push(input.len());
 
     let mut i = 0usize;
     while i < chars.len() {
+        let start = i;
         match chars[i] {
-            '+' => tokens.push(Token::Operator(Plus)),
-            '-' => tokens.push(Token::Operator(Minus)),
-            '*' => tokens.push(Token::Operator(Star)),
-            '/' => tokens.push(Token::Operator(Slash)),
-            '^' => tokens.push(Token::Operator(Caret)),
-            '(' => tokens.push(Token::Operator(LParen)),
-            ')' => tokens.push(Token::Operator(RParen)),
+            '+' => tokens.push((Token::Operator(Plus), byte_offsets[i]..byte_offsets[i + 1])),
+            '-' => tokens.push((Token::Operator(Minus), byte_offsets[i]..byte_offsets[i + 1])),
+            '*' => tokens.push((Token::Operator(Star), byte_offsets[i]..byte_offsets[i + 1])),
+            '/' => tokens.push((Token::Operator(Slash), byte_offsets[i]..byte_offsets[i + 1])),
+            '^' => tokens.push((Token::Operator(Caret), byte_offsets[i]..byte_offsets[i + 1])),
+            '!' => {
+                if i + 1 < chars.len() && chars[i + 1] == '=' {
+                    i += 1;
+                    tokens.push((Token::Operator(Ne), byte_offsets[start]..byte_offsets[i + 1]));
+                } else {
+                    tokens.push((Token::Operator(Bang), byte_offsets[i]..byte_offsets[i + 1]));
+                }
+            }
+            '=' => {
+                if i + 1 < chars.len() && chars[i + 1] == '=' {
+                    i += 1;
+                    tokens.push((Token::Operator(EqEq), byte_offsets[start]..byte_offsets[i + 1]));
+                } else {
+                    tokens.push((Token::Operator(Equals), byte_offsets[i]..byte_offsets[i + 1]));
+                }
+            }
+            '<' => {
+                if i + 1 < chars.len() && chars[i + 1] == '=' {
+                    i += 1;
+                    tokens.push((Token::Operator(Le), byte_offsets[start]..byte_offsets[i + 1]));
+                } else {
+                    tokens.push((Token::Operator(Lt), byte_offsets[i]..byte_offsets[i + 1]));
+                }
+            }
+            '>' => {
+                if i + 1 < chars.len() && chars[i + 1] == '=' {
+                    i += 1;
+                    tokens.push((Token::Operator(Ge), byte_offsets[start]..byte_offsets[i + 1]));
+                } else {
+                    tokens.push((Token::Operator(Gt), byte_offsets[i]..byte_offsets[i + 1]));
+                }
+            }
+            ',' => tokens.push((Token::Operator(Comma), byte_offsets[i]..byte_offsets[i + 1])),
+            '(' => tokens.push((Token::Operator(LParen), byte_offsets[i]..byte_offsets[i + 1])),
+            ')' => tokens.push((Token::Operator(RParen), byte_offsets[i]..byte_offsets[i + 1])),
             c => {
                 if c.is_whitespace() {
-                    break;
+                    i += 1;
+                    continue;
                 }
 
-                if c.is_digit(10) || c == '.' {
+                if c.is_ascii_digit() || c == '.' {
                     let mut number_string = c.to_string();
-                    
+
                     i += 1;
-                    while i < chars.len() && (chars[i].is_digit(10) || chars[i] == '.') {
+                    while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
                         number_string.push(chars[i]);
                         i += 1;
                     }
 
-                    let number;
-                    match number_string.parse::<f64>() {
-                        Ok(num) => number = num,
-                        _ => return Err(LexerError::InvalidNumber(number_string)),
+                    let number = match number_string.parse::<f64>() {
+                        Ok(num) => num,
+                        _ => {
+                            return Err(LexerError::InvalidNumber(
+                                number_string,
+                                byte_offsets[start]..byte_offsets[i],
+                            ))
+                        }
+                    };
+
+                    // A number directly followed by `i` is an imaginary literal, e.g. `3i`.
+                    if i < chars.len() && chars[i] == 'i' {
+                        i += 1;
+                        tokens.push((
+                            Token::ImaginaryNumber(number),
+                            byte_offsets[start]..byte_offsets[i],
+                        ));
+                    } else {
+                        tokens.push((Token::Number(number), byte_offsets[start]..byte_offsets[i]));
                     }
 
-                    tokens.push(Token::Number(number));
+                    continue; // we i += 1 at end of while
+                } else if c.is_alphabetic() || c == '_' {
+                    let mut ident = c.to_string();
+
+                    i += 1;
+                    while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                        ident.push(chars[i]);
+                        i += 1;
+                    }
+
+                    tokens.push((Token::Identifier(ident), byte_offsets[start]..byte_offsets[i]));
 
                     continue; // we i += 1 at end of while
                 } else {
-                    return Err(LexerError::InvalidCharacter(c));
+                    return Err(LexerError::InvalidCharacter(
+                        c,
+                        byte_offsets[start]..byte_offsets[start + 1],
+                    ));
                 }
             }
         }
         i += 1;
     }
-    
+
     Ok(tokens)
-}
\ No newline at end of file
+}
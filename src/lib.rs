@@ -0,0 +1,29 @@
+//! `rsc` is a small math expression parser and evaluator.
+//!
+//! ```
+//! use rsc::computer::Computer;
+//!
+//! let mut computer = Computer::<f64>::new(std::f64::consts::PI, std::f64::consts::E);
+//! assert_eq!(computer.eval("2 + 2").unwrap(), 4.0);
+//! ```
+
+pub mod complex;
+pub mod computer;
+pub mod diagnostics;
+pub mod lexer;
+pub mod parser;
+pub mod vm;
+
+use lexer::LexerError;
+use parser::ParserError;
+
+use self::computer::ComputeError;
+
+/// The top-level error returned by [`Computer::eval`](computer::Computer::eval),
+/// wrapping whichever stage of the pipeline (lexing, parsing, computing) failed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalError {
+    LexerError(LexerError),
+    ParserError(ParserError),
+    ComputeError(ComputeError),
+}
@@ -0,0 +1,96 @@
+//! Interactive command-line calculator built on top of the `rsc` library.
+//!
+//! Run `rsc` with no arguments for a REPL with line editing and history, or
+//! `rsc "2 + 2"` to evaluate a single expression and exit.
+
+use std::env;
+
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+use rsc::computer::Computer;
+use rsc::diagnostics::render_span;
+use rsc::EvalError;
+
+fn new_computer() -> Computer<f64> {
+    Computer::new(std::f64::consts::PI, std::f64::consts::E)
+}
+
+fn report_error(input: &str, err: &EvalError) {
+    eprintln!("{}", render_span(input, err));
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if args.is_empty() {
+        run_repl();
+        return;
+    }
+
+    let input = args.join(" ");
+    let mut computer = new_computer();
+    match computer.eval(&input) {
+        Ok(result) => println!("{}", result),
+        Err(err) => {
+            report_error(&input, &err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_repl() {
+    let mut computer = new_computer();
+    let mut editor = DefaultEditor::new().expect("failed to initialize line editor");
+
+    loop {
+        match editor.readline("rsc> ") {
+            Ok(line) => {
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                editor.add_history_entry(line).ok();
+
+                match line {
+                    ":clear" => {
+                        computer = new_computer();
+                        println!("variables cleared");
+                    }
+                    ":vars" => print_vars(&computer),
+                    _ => match computer.eval(line) {
+                        Ok(result) => println!("{}", result),
+                        Err(err) => report_error(line, &err),
+                    },
+                }
+            }
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+            Err(err) => {
+                eprintln!("readline error: {:?}", err);
+                break;
+            }
+        }
+    }
+}
+
+/// Prints every non-constant variable currently defined, i.e. everything
+/// except `pi`, `e`, and `ans`.
+fn print_vars(computer: &Computer<f64>) {
+    let mut names: Vec<&String> = computer
+        .variables
+        .iter()
+        .filter(|(_, (_, is_constant))| !is_constant)
+        .map(|(name, _)| name)
+        .collect();
+    names.sort();
+
+    if names.is_empty() {
+        println!("(no variables defined)");
+        return;
+    }
+
+    for name in names {
+        let (value, _) = &computer.variables[name];
+        println!("{} = {}", name, value);
+    }
+}
@@ -0,0 +1,315 @@
+//! Turns a stream of [`Token`]s from the lexer into an [`Expr`] tree ready
+//! for the computer to evaluate.
+
+use crate::computer::Num;
+use crate::lexer::*;
+
+use std::ops::Range;
+
+/// Pairs a parsed node with the byte-offset span of source text it came
+/// from, so a later compute error can point back at the exact subexpression
+/// that caused it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned<T> {
+    pub node: T,
+    pub span: Range<usize>,
+}
+
+impl<T> Spanned<T> {
+    fn new(node: T, span: Range<usize>) -> Spanned<T> {
+        Spanned { node, span }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr<T> {
+    Constant(T),
+    Identifier(String),
+    Neg(Box<Spanned<Expr<T>>>),
+    BinOp(Operator, Box<Spanned<Expr<T>>>, Box<Spanned<Expr<T>>>),
+    Function(Function, Vec<Spanned<Expr<T>>>),
+    Assignment(String, Box<Spanned<Expr<T>>>),
+    Pow(Box<Spanned<Expr<T>>>, Box<Spanned<Expr<T>>>),
+    Factorial(Box<Spanned<Expr<T>>>),
+}
+
+/// The set of functions the language understands, some unary and some
+/// binary. Actual argument-count validation happens at evaluation time in
+/// `computer::call_function` (used by both `Computer::compute` and the VM),
+/// since that's where `ComputeError::WrongArgumentCount` is raised.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Function {
+    Sqrt,
+    Sin,
+    Cos,
+    Tan,
+    Log,
+    Abs,
+    Min,
+    Max,
+    Hypot,
+    Root,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Function> {
+        match name {
+            "sqrt" => Some(Function::Sqrt),
+            "sin" => Some(Function::Sin),
+            "cos" => Some(Function::Cos),
+            "tan" => Some(Function::Tan),
+            "log" => Some(Function::Log),
+            "abs" => Some(Function::Abs),
+            "min" => Some(Function::Min),
+            "max" => Some(Function::Max),
+            "hypot" => Some(Function::Hypot),
+            "root" => Some(Function::Root),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Function::Sqrt => "sqrt",
+            Function::Sin => "sin",
+            Function::Cos => "cos",
+            Function::Tan => "tan",
+            Function::Log => "log",
+            Function::Abs => "abs",
+            Function::Min => "min",
+            Function::Max => "max",
+            Function::Hypot => "hypot",
+            Function::Root => "root",
+        }
+    }
+
+    /// The argument counts this function accepts. `log` is the only function
+    /// with more than one valid arity: `log(x)` (natural log) and
+    /// `log(base, x)`.
+    pub fn arities(&self) -> &'static [usize] {
+        match self {
+            Function::Sqrt | Function::Sin | Function::Cos | Function::Tan | Function::Abs => &[1],
+            Function::Log => &[1, 2],
+            Function::Min | Function::Max | Function::Hypot | Function::Root => &[2],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParserError {
+    UnexpectedToken(Token, Range<usize>),
+    UnexpectedEndOfInput(usize),
+    UnknownFunction(String, Range<usize>),
+    InvalidNumber(String, Range<usize>),
+}
+use self::ParserError::*;
+
+struct Parser<'a> {
+    tokens: &'a [(Token, Range<usize>)],
+    pos: usize,
+    /// The byte offset just past the last token, used as the span for
+    /// errors raised when input ends early.
+    end: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&(Token, Range<usize>)> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&(Token, Range<usize>)> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn expect_operator(&mut self, op: Operator) -> Result<Range<usize>, ParserError> {
+        match self.next() {
+            Some((Token::Operator(found), span)) if *found == op => Ok(span.clone()),
+            Some((token, span)) => Err(UnexpectedToken(token.clone(), span.clone())),
+            None => Err(UnexpectedEndOfInput(self.end)),
+        }
+    }
+
+    fn parse_expr<T: std::str::FromStr + Num>(&mut self) -> Result<Spanned<Expr<T>>, ParserError> {
+        // An assignment is just `identifier = expr`, checked for up front so
+        // the rest of the grammar doesn't need to know about it.
+        if let Some((Token::Identifier(name), name_span)) = self.peek() {
+            if let Some((Token::Operator(Operator::Equals), _)) = self.tokens.get(self.pos + 1) {
+                let name = name.clone();
+                let start = name_span.start;
+                self.pos += 2;
+                let value = self.parse_expr()?;
+                let span = start..value.span.end;
+                return Ok(Spanned::new(Expr::Assignment(name, Box::new(value)), span));
+            }
+        }
+
+        self.parse_relational()
+    }
+
+    /// Relational and equality operators bind looser than `+`/`-`, so
+    /// `a + 1 > b` parses as `(a + 1) > b`.
+    fn parse_relational<T: std::str::FromStr + Num>(&mut self) -> Result<Spanned<Expr<T>>, ParserError> {
+        let mut expr = self.parse_additive()?;
+
+        while let Some((
+            Token::Operator(
+                op @ (Operator::Lt | Operator::Le | Operator::Gt | Operator::Ge | Operator::EqEq | Operator::Ne),
+            ),
+            _,
+        )) = self.peek()
+        {
+            let op = *op;
+            self.next();
+            let rhs = self.parse_additive()?;
+            let span = expr.span.start..rhs.span.end;
+            expr = Spanned::new(Expr::BinOp(op, Box::new(expr), Box::new(rhs)), span);
+        }
+
+        Ok(expr)
+    }
+
+    fn parse_additive<T: std::str::FromStr + Num>(&mut self) -> Result<Spanned<Expr<T>>, ParserError> {
+        let mut expr = self.parse_multiplicative()?;
+
+        while let Some((Token::Operator(op @ Operator::Plus), _))
+        | Some((Token::Operator(op @ Operator::Minus), _)) = self.peek()
+        {
+            let op = *op;
+            self.next();
+            let rhs = self.parse_multiplicative()?;
+            let span = expr.span.start..rhs.span.end;
+            expr = Spanned::new(Expr::BinOp(op, Box::new(expr), Box::new(rhs)), span);
+        }
+
+        Ok(expr)
+    }
+
+    fn parse_multiplicative<T: std::str::FromStr + Num>(&mut self) -> Result<Spanned<Expr<T>>, ParserError> {
+        let mut expr = self.parse_unary()?;
+
+        while let Some((Token::Operator(op @ Operator::Star), _))
+        | Some((Token::Operator(op @ Operator::Slash), _)) = self.peek()
+        {
+            let op = *op;
+            self.next();
+            let rhs = self.parse_unary()?;
+            let span = expr.span.start..rhs.span.end;
+            expr = Spanned::new(Expr::BinOp(op, Box::new(expr), Box::new(rhs)), span);
+        }
+
+        Ok(expr)
+    }
+
+    fn parse_unary<T: std::str::FromStr + Num>(&mut self) -> Result<Spanned<Expr<T>>, ParserError> {
+        if let Some((Token::Operator(Operator::Minus), minus_span)) = self.peek() {
+            let start = minus_span.start;
+            self.next();
+            let expr = self.parse_unary()?;
+            let span = start..expr.span.end;
+            return Ok(Spanned::new(Expr::Neg(Box::new(expr)), span));
+        }
+
+        self.parse_power()
+    }
+
+    fn parse_power<T: std::str::FromStr + Num>(&mut self) -> Result<Spanned<Expr<T>>, ParserError> {
+        let base = self.parse_postfix()?;
+
+        if let Some((Token::Operator(Operator::Caret), _)) = self.peek() {
+            self.next();
+            // Right-associative: `2 ^ 3 ^ 2` is `2 ^ (3 ^ 2)`.
+            let exponent = self.parse_unary()?;
+            let span = base.span.start..exponent.span.end;
+            return Ok(Spanned::new(Expr::Pow(Box::new(base), Box::new(exponent)), span));
+        }
+
+        Ok(base)
+    }
+
+    fn parse_postfix<T: std::str::FromStr + Num>(&mut self) -> Result<Spanned<Expr<T>>, ParserError> {
+        let mut expr = self.parse_primary()?;
+
+        while let Some((Token::Operator(Operator::Bang), bang_span)) = self.peek() {
+            let end = bang_span.end;
+            self.next();
+            let span = expr.span.start..end;
+            expr = Spanned::new(Expr::Factorial(Box::new(expr)), span);
+        }
+
+        Ok(expr)
+    }
+
+    /// Parses the comma-separated argument list of a function call, assuming
+    /// the opening `(` has already been consumed, up to and including the
+    /// closing `)`.
+    fn parse_call_args<T: std::str::FromStr + Num>(&mut self) -> Result<Vec<Spanned<Expr<T>>>, ParserError> {
+        let mut args = Vec::new();
+
+        if let Some((Token::Operator(Operator::RParen), _)) = self.peek() {
+            self.next();
+            return Ok(args);
+        }
+
+        args.push(self.parse_expr()?);
+        while let Some((Token::Operator(Operator::Comma), _)) = self.peek() {
+            self.next();
+            args.push(self.parse_expr()?);
+        }
+
+        self.expect_operator(Operator::RParen)?;
+        Ok(args)
+    }
+
+    fn parse_primary<T: std::str::FromStr + Num>(&mut self) -> Result<Spanned<Expr<T>>, ParserError> {
+        match self.next().cloned() {
+            Some((Token::Number(num), span)) => num
+                .to_string()
+                .parse::<T>()
+                .map(|value| Spanned::new(Expr::Constant(value), span.clone()))
+                .map_err(|_| InvalidNumber(num.to_string(), span)),
+            Some((Token::ImaginaryNumber(im), span)) => {
+                Ok(Spanned::new(Expr::Constant(T::from_imaginary(im)), span))
+            }
+            Some((Token::Identifier(name), span)) => {
+                if let Some((Token::Operator(Operator::LParen), _)) = self.peek() {
+                    match Function::from_name(&name) {
+                        Some(function) => {
+                            self.next();
+                            let args = self.parse_call_args()?;
+                            // `parse_call_args` consumes through the closing `)`, so the
+                            // previous token's span ends the call's own span.
+                            let end = self.tokens[self.pos - 1].1.end;
+                            Ok(Spanned::new(Expr::Function(function, args), span.start..end))
+                        }
+                        None => Err(UnknownFunction(name, span)),
+                    }
+                } else {
+                    Ok(Spanned::new(Expr::Identifier(name), span))
+                }
+            }
+            Some((Token::Operator(Operator::LParen), lparen_span)) => {
+                let expr = self.parse_expr()?;
+                let rparen_span = self.expect_operator(Operator::RParen)?;
+                Ok(Spanned::new(expr.node, lparen_span.start..rparen_span.end))
+            }
+            Some((token, span)) => Err(UnexpectedToken(token, span)),
+            None => Err(UnexpectedEndOfInput(self.end)),
+        }
+    }
+}
+
+/// Parse a full token stream (as produced by [`tokenize`](crate::lexer::tokenize))
+/// into an [`Expr`] tree, with each node spanning the source bytes it was
+/// parsed from.
+pub fn parse<T: std::str::FromStr + Num>(tokens: &[(Token, Range<usize>)]) -> Result<Spanned<Expr<T>>, ParserError> {
+    let end = tokens.last().map(|(_, span)| span.end).unwrap_or(0);
+    let mut parser = Parser { tokens, pos: 0, end };
+    let expr = parser.parse_expr()?;
+
+    match parser.peek() {
+        Some((token, span)) => Err(UnexpectedToken(token.clone(), span.clone())),
+        None => Ok(expr),
+    }
+}
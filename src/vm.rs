@@ -0,0 +1,305 @@
+//! Compiles an [`Expr`] tree into a flat bytecode [`Program`] for a small
+//! stack machine, so callers who evaluate the same expression many times
+//! (loops, plotting, tables of `f(x)`) don't have to re-walk the AST on
+//! every call. `Computer::compute` remains the tree-walking path; this is
+//! an opt-in fast path.
+
+use crate::computer::{call_function, factorial, ComputeError, Computer, Num};
+use crate::lexer::Operator;
+use crate::parser::{Expr, Function, Spanned};
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Op<T> {
+    PushConst(T),
+    LoadVar(usize),
+    StoreVar(usize),
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+    Neg,
+    /// A relational/equality comparison; `operator` is one of
+    /// `Lt`/`Le`/`Gt`/`Ge`/`EqEq`/`Ne`.
+    Cmp(Operator),
+    /// Calls `function` with the top `argc` stack values (in argument order).
+    Call(Function, usize),
+    Factorial,
+}
+
+/// A compiled expression, ready to be run against a [`Computer`] as many
+/// times as needed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Program<T> {
+    ops: Vec<Op<T>>,
+    /// Slot table: variable names resolved up front, indexed by the
+    /// `LoadVar`/`StoreVar` operand.
+    var_names: Vec<String>,
+}
+
+struct Compiler {
+    var_names: Vec<String>,
+}
+
+impl Compiler {
+    fn slot_for(&mut self, name: &str) -> usize {
+        match self.var_names.iter().position(|n| n == name) {
+            Some(slot) => slot,
+            None => {
+                self.var_names.push(name.to_string());
+                self.var_names.len() - 1
+            }
+        }
+    }
+
+    fn compile_expr<T: Clone>(&mut self, expr: &Spanned<Expr<T>>, ops: &mut Vec<Op<T>>) {
+        match &expr.node {
+            Expr::Constant(value) => ops.push(Op::PushConst(value.clone())),
+            Expr::Identifier(name) => {
+                let slot = self.slot_for(name);
+                ops.push(Op::LoadVar(slot));
+            }
+            Expr::Neg(inner) => {
+                self.compile_expr(inner, ops);
+                ops.push(Op::Neg);
+            }
+            Expr::BinOp(operator, lhs, rhs) => {
+                self.compile_expr(lhs, ops);
+                self.compile_expr(rhs, ops);
+                ops.push(match operator {
+                    Operator::Plus => Op::Add,
+                    Operator::Minus => Op::Sub,
+                    Operator::Star => Op::Mul,
+                    Operator::Slash => Op::Div,
+                    Operator::Lt
+                    | Operator::Le
+                    | Operator::Gt
+                    | Operator::Ge
+                    | Operator::EqEq
+                    | Operator::Ne => Op::Cmp(*operator),
+                    _ => unreachable!("not a valid BinOp operator"),
+                });
+            }
+            Expr::Function(function, args) => {
+                for arg in args {
+                    self.compile_expr(arg, ops);
+                }
+                ops.push(Op::Call(*function, args.len()));
+            }
+            Expr::Assignment(name, value) => {
+                self.compile_expr(value, ops);
+                let slot = self.slot_for(name);
+                ops.push(Op::StoreVar(slot));
+            }
+            Expr::Pow(base, exponent) => {
+                self.compile_expr(base, ops);
+                self.compile_expr(exponent, ops);
+                ops.push(Op::Pow);
+            }
+            Expr::Factorial(inner) => {
+                self.compile_expr(inner, ops);
+                ops.push(Op::Factorial);
+            }
+        }
+    }
+}
+
+/// Lowers an `Expr` tree into a flat [`Program`], resolving identifiers to
+/// slot indices up front.
+///
+/// ```
+/// use rsc::computer::Computer;
+/// use rsc::parser::parse;
+/// use rsc::vm::compile;
+///
+/// let mut computer = Computer::<f64>::new(std::f64::consts::PI, std::f64::consts::E);
+/// let tokens = rsc::lexer::tokenize("x * x + 1").unwrap();
+/// let ast = parse(&tokens).unwrap();
+/// let program = compile(&ast);
+///
+/// // Compiled once, the same program can run against different `x` values
+/// // without re-parsing or re-walking the AST.
+/// computer.eval("x = 2").unwrap();
+/// assert_eq!(program.run(&mut computer).unwrap(), 5.0);
+/// computer.eval("x = 3").unwrap();
+/// assert_eq!(program.run(&mut computer).unwrap(), 10.0);
+/// ```
+pub fn compile<T: Clone>(expr: &Spanned<Expr<T>>) -> Program<T> {
+    let mut compiler = Compiler {
+        var_names: Vec::new(),
+    };
+    let mut ops = Vec::new();
+    compiler.compile_expr(expr, &mut ops);
+    Program {
+        ops,
+        var_names: compiler.var_names,
+    }
+}
+
+impl<T> Program<T>
+where
+    T: Num
+        + Clone
+        + PartialOrd
+        + Neg<Output = T>
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Output = T>
+        + Div<Output = T>,
+{
+    /// Executes the program's ops against an operand stack, reading and
+    /// writing `computer`'s variables for `LoadVar`/`StoreVar`.
+    /// ```
+    /// use rsc::computer::Computer;
+    /// use rsc::parser::parse;
+    /// use rsc::vm::compile;
+    ///
+    /// let mut computer = Computer::<f64>::new(std::f64::consts::PI, std::f64::consts::E);
+    ///
+    /// // `Call` (a multi-arg function), `Factorial`, and `Cmp` all compile
+    /// // down to their own `Op` and agree with the tree-walking `Computer`.
+    /// let tokens = rsc::lexer::tokenize("min(3, 7) + 2! + (1 < 2)").unwrap();
+    /// let ast = parse(&tokens).unwrap();
+    /// let program = compile(&ast);
+    /// assert_eq!(program.run(&mut computer).unwrap(), 6.0);
+    ///
+    /// // A compiled arity error surfaces as `WrongArgumentCount`, not a panic.
+    /// let tokens = rsc::lexer::tokenize("min(1, 2, 3)").unwrap();
+    /// let ast = parse(&tokens).unwrap();
+    /// let program = compile(&ast);
+    /// assert!(matches!(
+    ///     program.run(&mut computer),
+    ///     Err(rsc::computer::ComputeError::WrongArgumentCount { expected: 2, actual: 3, .. })
+    /// ));
+    /// ```
+    pub fn run(&self, computer: &mut Computer<T>) -> Result<T, ComputeError> {
+        let mut stack: Vec<T> = Vec::new();
+
+        for op in &self.ops {
+            match op {
+                Op::PushConst(value) => stack.push(value.clone()),
+                Op::LoadVar(slot) => {
+                    let name = &self.var_names[*slot];
+                    match computer.variables.get(name) {
+                        Some((value, _)) => stack.push(value.clone()),
+                        None => return Err(ComputeError::UnrecognizedIdentifier(name.clone(), 0..0)),
+                    }
+                }
+                Op::StoreVar(slot) => {
+                    let name = &self.var_names[*slot];
+                    let value = stack.pop().expect("stack underflow: StoreVar");
+                    if computer
+                        .variables
+                        .get(name)
+                        .is_some_and(|(_, is_constant)| *is_constant)
+                    {
+                        return Err(ComputeError::VariableIsConstant(name.clone(), 0..0));
+                    }
+                    computer
+                        .variables
+                        .insert(name.clone(), (value.clone(), false));
+                    stack.push(value);
+                }
+                Op::Add => {
+                    let rhs = stack.pop().expect("stack underflow: Add");
+                    let lhs = stack.pop().expect("stack underflow: Add");
+                    stack.push(lhs + rhs);
+                }
+                Op::Sub => {
+                    let rhs = stack.pop().expect("stack underflow: Sub");
+                    let lhs = stack.pop().expect("stack underflow: Sub");
+                    stack.push(lhs - rhs);
+                }
+                Op::Mul => {
+                    let rhs = stack.pop().expect("stack underflow: Mul");
+                    let lhs = stack.pop().expect("stack underflow: Mul");
+                    stack.push(lhs * rhs);
+                }
+                Op::Div => {
+                    let rhs = stack.pop().expect("stack underflow: Div");
+                    let lhs = stack.pop().expect("stack underflow: Div");
+                    stack.push(lhs / rhs);
+                }
+                Op::Pow => {
+                    let rhs = stack.pop().expect("stack underflow: Pow");
+                    let lhs = stack.pop().expect("stack underflow: Pow");
+                    stack.push(lhs.pow(&rhs));
+                }
+                Op::Neg => {
+                    let value = stack.pop().expect("stack underflow: Neg");
+                    stack.push(-value);
+                }
+                Op::Cmp(operator) => {
+                    let rhs = stack.pop().expect("stack underflow: Cmp");
+                    let lhs = stack.pop().expect("stack underflow: Cmp");
+                    let result = match operator {
+                        Operator::Lt => lhs < rhs,
+                        Operator::Le => lhs <= rhs,
+                        Operator::Gt => lhs > rhs,
+                        Operator::Ge => lhs >= rhs,
+                        Operator::EqEq => lhs == rhs,
+                        Operator::Ne => lhs != rhs,
+                        _ => unreachable!("only relational operators compile to Cmp"),
+                    };
+                    stack.push(if result { T::one() } else { T::zero() });
+                }
+                Op::Call(function, argc) => {
+                    let start = stack.len() - argc;
+                    let args: Vec<T> = stack.split_off(start);
+                    // A compiled program has no source spans left to report, unlike
+                    // the tree-walking path.
+                    stack.push(call_function(*function, &args, 0..0)?);
+                }
+                Op::Factorial => {
+                    let value = stack.pop().expect("stack underflow: Factorial");
+                    stack.push(factorial(value, 0..0)?);
+                }
+            }
+        }
+
+        let result = stack.pop().expect("stack underflow: empty program");
+        computer
+            .variables
+            .insert(String::from("ans"), (result.clone(), true));
+        Ok(result)
+    }
+
+    /// Returns an offset/opcode/operand table as a string, for debugging
+    /// compiled programs.
+    /// ```
+    /// use rsc::parser::parse;
+    /// use rsc::vm::compile;
+    ///
+    /// let tokens = rsc::lexer::tokenize("2 + 3").unwrap();
+    /// let ast = parse::<f64>(&tokens).unwrap();
+    /// let program = compile(&ast);
+    ///
+    /// assert!(program.disassemble().contains("Add"));
+    /// ```
+    pub fn disassemble(&self) -> String
+    where
+        T: std::fmt::Debug,
+    {
+        let mut out = String::new();
+        for (offset, op) in self.ops.iter().enumerate() {
+            let (name, operand) = match op {
+                Op::PushConst(value) => ("PushConst", format!("{:?}", value)),
+                Op::LoadVar(slot) => ("LoadVar", format!("{} ; {}", slot, self.var_names[*slot])),
+                Op::StoreVar(slot) => ("StoreVar", format!("{} ; {}", slot, self.var_names[*slot])),
+                Op::Add => ("Add", String::new()),
+                Op::Sub => ("Sub", String::new()),
+                Op::Mul => ("Mul", String::new()),
+                Op::Div => ("Div", String::new()),
+                Op::Pow => ("Pow", String::new()),
+                Op::Neg => ("Neg", String::new()),
+                Op::Cmp(operator) => ("Cmp", format!("{:?}", operator)),
+                Op::Call(function, argc) => ("Call", format!("{} ; {} arg(s)", function.name(), argc)),
+                Op::Factorial => ("Factorial", String::new()),
+            };
+            out.push_str(&format!("{:>4}  {:<10} {}\n", offset, name, operand));
+        }
+        out
+    }
+}
@@ -0,0 +1,51 @@
+//! Integration tests that drive the `rsc` binary as a subprocess, since
+//! `main.rs` isn't part of the library and can't be reached by a doctest.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[test]
+fn one_shot_eval_prints_result_and_exits_zero() {
+    let output = Command::new(env!("CARGO_BIN_EXE_rsc"))
+        .arg("2 + 2")
+        .output()
+        .expect("failed to run rsc");
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "4");
+}
+
+#[test]
+fn one_shot_eval_error_exits_nonzero() {
+    let output = Command::new(env!("CARGO_BIN_EXE_rsc"))
+        .arg("1 + foo")
+        .output()
+        .expect("failed to run rsc");
+
+    assert!(!output.status.success());
+}
+
+#[test]
+fn repl_persists_variables_and_handles_vars_and_clear() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rsc"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run rsc");
+
+    {
+        let stdin = child.stdin.as_mut().expect("child has no stdin");
+        writeln!(stdin, "a = 2").unwrap();
+        writeln!(stdin, "ans * 3").unwrap();
+        writeln!(stdin, ":vars").unwrap();
+        writeln!(stdin, ":clear").unwrap();
+        writeln!(stdin, ":vars").unwrap();
+    } // drop stdin, sending EOF so the REPL exits
+
+    let output = child.wait_with_output().expect("failed to wait on rsc");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("a = 2"), "stdout was: {}", stdout);
+    assert!(stdout.contains("variables cleared"), "stdout was: {}", stdout);
+    assert!(stdout.contains("(no variables defined)"), "stdout was: {}", stdout);
+}